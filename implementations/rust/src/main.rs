@@ -1,8 +1,15 @@
 // nano-opencode: Minimal AI coding agent in Rust (~120 LOC)
 // Usage: ANTHROPIC_API_KEY=sk-... cargo run "your prompt"
+// Streaming: STREAM=1 cargo run "your prompt"
+// Other providers: PROVIDER=openai OPENAI_API_KEY=sk-... cargo run "your prompt" (also: cohere)
+// Non-interactive: YOLO=1 cargo run "your prompt" (auto-approves write_file/edit_file/bash)
+// Resume a conversation: cargo run -- --session chat.json "your prompt" (or SESSION=chat.json)
 // Build: cargo build --release
 
 use std::{env, fs, process::Command, io::Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
@@ -11,11 +18,12 @@ const TOOLS: &str = r#"[
   {"name":"write_file","description":"Write file","input_schema":{"type":"object","properties":{"path":{"type":"string"},"content":{"type":"string"}},"required":["path","content"]}},
   {"name":"edit_file","description":"Edit file","input_schema":{"type":"object","properties":{"path":{"type":"string"},"old_string":{"type":"string"},"new_string":{"type":"string"}},"required":["path","old_string","new_string"]}},
   {"name":"bash","description":"Run command","input_schema":{"type":"object","properties":{"command":{"type":"string"}},"required":["command"]}},
-  {"name":"list_dir","description":"List directory","input_schema":{"type":"object","properties":{"path":{"type":"string"}},"required":["path"]}}
+  {"name":"list_dir","description":"List directory","input_schema":{"type":"object","properties":{"path":{"type":"string"}},"required":["path"]}},
+  {"name":"read_image","description":"Read a local image file or data: URL and attach it to the conversation","input_schema":{"type":"object","properties":{"path":{"type":"string"}},"required":["path"]}}
 ]"#;
 
 #[derive(Serialize)]
-struct Request { model: String, max_tokens: u32, tools: Value, messages: Vec<Message>, system: String }
+struct Request { model: String, max_tokens: u32, tools: Value, messages: Vec<Message>, system: String, stream: bool }
 
 #[derive(Serialize, Deserialize, Clone)]
 struct Message { role: String, content: Value }
@@ -23,9 +31,170 @@ struct Message { role: String, content: Value }
 #[derive(Deserialize)]
 struct Response { content: Vec<Block>, stop_reason: String }
 
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 struct Block { r#type: String, id: Option<String>, name: Option<String>, input: Option<Value>, text: Option<String> }
 
+// Which wire format to speak; `call()` maps `Message`/`Block` to and from each provider's shape.
+#[derive(Clone, Copy, PartialEq)]
+enum Provider { Anthropic, OpenAI, Cohere }
+
+impl Provider {
+    fn from_env() -> Self {
+        match env::var("PROVIDER").as_deref() {
+            Ok("openai") => Provider::OpenAI,
+            Ok("cohere") => Provider::Cohere,
+            _ => Provider::Anthropic,
+        }
+    }
+
+    fn default_model(&self) -> &'static str {
+        match self {
+            Provider::Anthropic => "claude-sonnet-4-20250514",
+            Provider::OpenAI => "gpt-4o",
+            Provider::Cohere => "command-r-plus",
+        }
+    }
+}
+
+// Returns (media_type, base64 data) if this tool_result content is an image_block() shape.
+fn as_image(content: &Value) -> Option<(&str, &str)> {
+    let block = content.as_array()?.first()?;
+    if block["type"] != "image" { return None; }
+    Some((block["source"]["media_type"].as_str()?, block["source"]["data"].as_str()?))
+}
+
+fn openai_tools(tools: &Value) -> Value {
+    json!(tools.as_array().unwrap().iter().map(|t| json!({
+        "type": "function",
+        "function": {"name": t["name"], "description": t["description"], "parameters": t["input_schema"]}
+    })).collect::<Vec<_>>())
+}
+
+fn openai_messages(system: &str, messages: &[Message]) -> Value {
+    let mut out = vec![json!({"role": "system", "content": system})];
+    for m in messages {
+        if m.role == "assistant" {
+            let blocks = m.content.as_array().cloned().unwrap_or_default();
+            let text: String = blocks.iter().filter(|b| b["type"] == "text").filter_map(|b| b["text"].as_str()).collect();
+            let tool_calls: Vec<Value> = blocks.iter().filter(|b| b["type"] == "tool_use").map(|b| json!({
+                "id": b["id"], "type": "function",
+                "function": {"name": b["name"], "arguments": serde_json::to_string(&b["input"]).unwrap_or_default()}
+            })).collect();
+            let mut msg = json!({"role": "assistant", "content": if text.is_empty() { Value::Null } else { json!(text) }});
+            if !tool_calls.is_empty() { msg["tool_calls"] = json!(tool_calls); }
+            out.push(msg);
+        } else if let Some(s) = m.content.as_str() {
+            out.push(json!({"role": "user", "content": s}));
+        } else if let Some(results) = m.content.as_array() {
+            for r in results {
+                match as_image(&r["content"]) {
+                    Some((media_type, data)) => {
+                        out.push(json!({"role": "tool", "tool_call_id": r["tool_use_id"], "content": "image attached in next message"}));
+                        out.push(json!({"role": "user", "content": [{"type": "image_url", "image_url": {"url": format!("data:{};base64,{}", media_type, data)}}]}));
+                    }
+                    None => out.push(json!({"role": "tool", "tool_call_id": r["tool_use_id"], "content": r["content"]})),
+                }
+            }
+        }
+    }
+    json!(out)
+}
+
+fn parse_openai(body: Value) -> Result<Response, String> {
+    let message = &body["choices"][0]["message"];
+    let finish = body["choices"][0]["finish_reason"].as_str().unwrap_or("stop");
+    let mut content = Vec::new();
+
+    if let Some(text) = message["content"].as_str().filter(|t| !t.is_empty()) {
+        content.push(Block { r#type: "text".to_string(), id: None, name: None, input: None, text: Some(text.to_string()) });
+    }
+    for c in message["tool_calls"].as_array().cloned().unwrap_or_default() {
+        let args = serde_json::from_str(c["function"]["arguments"].as_str().unwrap_or("{}")).unwrap_or(json!({}));
+        content.push(Block {
+            r#type: "tool_use".to_string(),
+            id: c["id"].as_str().map(|s| s.to_string()),
+            name: c["function"]["name"].as_str().map(|s| s.to_string()),
+            input: Some(args),
+            text: None,
+        });
+    }
+
+    let stop_reason = if finish == "tool_calls" { "tool_use" } else { "end_turn" }.to_string();
+    Ok(Response { content, stop_reason })
+}
+
+fn cohere_tools(tools: &Value) -> Value {
+    json!(tools.as_array().unwrap().iter().map(|t| {
+        let required: Vec<&str> = t["input_schema"]["required"].as_array().map(|a| a.iter().filter_map(|v| v.as_str()).collect()).unwrap_or_default();
+        let defs: serde_json::Map<String, Value> = t["input_schema"]["properties"].as_object().cloned().unwrap_or_default().into_iter()
+            .map(|(k, v)| {
+                let req = required.contains(&k.as_str());
+                (k, json!({"description": v["description"].as_str().unwrap_or(""), "type": v["type"].as_str().unwrap_or("string"), "required": req}))
+            }).collect();
+        json!({"name": t["name"], "description": t["description"], "parameter_definitions": defs})
+    }).collect::<Vec<_>>())
+}
+
+// Cohere splits the latest turn into `message` and the rest into `chat_history`, with tool
+// outputs passed back via a top-level `tool_results` field instead of folded into history.
+fn cohere_body(model: &str, system: &str, tools: &Value, messages: &[Message]) -> Value {
+    let mut history = Vec::new();
+    let mut message = String::new();
+    let mut tool_results = Vec::new();
+
+    for (i, m) in messages.iter().enumerate() {
+        let is_last = i == messages.len() - 1;
+        if m.role == "assistant" {
+            let blocks = m.content.as_array().cloned().unwrap_or_default();
+            let text: String = blocks.iter().filter(|b| b["type"] == "text").filter_map(|b| b["text"].as_str()).collect();
+            let calls: Vec<Value> = blocks.iter().filter(|b| b["type"] == "tool_use").map(|b| json!({"name": b["name"], "parameters": b["input"]})).collect();
+            let mut turn = json!({"role": "CHATBOT", "message": text});
+            if !calls.is_empty() { turn["tool_calls"] = json!(calls); }
+            history.push(turn);
+        } else if let Some(s) = m.content.as_str() {
+            if is_last { message = s.to_string(); } else { history.push(json!({"role": "USER", "message": s})); }
+        } else if let Some(results) = m.content.as_array() {
+            let prior = messages.get(i - 1).and_then(|pm| pm.content.as_array().cloned()).unwrap_or_default();
+            for r in results {
+                let id = r["tool_use_id"].as_str().unwrap_or("");
+                let call = prior.iter().find(|b| b["id"].as_str() == Some(id));
+                let name = call.map(|b| b["name"].clone()).unwrap_or(Value::Null);
+                let parameters = call.map(|b| b["input"].clone()).unwrap_or(Value::Null);
+                let result = match as_image(&r["content"]) {
+                    Some((media_type, data)) => json!(format!("data:{};base64,{}", media_type, data)),
+                    None => r["content"].clone(),
+                };
+                tool_results.push(json!({"call": {"name": name, "parameters": parameters}, "outputs": [{"result": result}]}));
+            }
+        }
+    }
+
+    let mut body = json!({ "model": model, "preamble": system, "chat_history": history, "message": message, "tools": tools });
+    if !tool_results.is_empty() { body["tool_results"] = json!(tool_results); }
+    body
+}
+
+fn parse_cohere(body: Value) -> Result<Response, String> {
+    let mut content = Vec::new();
+    if let Some(text) = body["text"].as_str().filter(|t| !t.is_empty()) {
+        content.push(Block { r#type: "text".to_string(), id: None, name: None, input: None, text: Some(text.to_string()) });
+    }
+
+    let calls = body["tool_calls"].as_array().cloned().unwrap_or_default();
+    for (i, c) in calls.iter().enumerate() {
+        content.push(Block {
+            r#type: "tool_use".to_string(),
+            id: Some(format!("cohere_call_{}", i)),
+            name: c["name"].as_str().map(|s| s.to_string()),
+            input: Some(c["parameters"].clone()),
+            text: None,
+        });
+    }
+
+    let stop_reason = if calls.is_empty() { "end_turn" } else { "tool_use" }.to_string();
+    Ok(Response { content, stop_reason })
+}
+
 fn run(name: &str, input: &Value) -> String {
     match name {
         "read_file" => fs::read_to_string(input["path"].as_str().unwrap_or(".")).unwrap_or_else(|e| format!("Error: {}", e)),
@@ -58,60 +227,298 @@ fn run(name: &str, input: &Value) -> String {
                     .collect::<Vec<_>>().join("\n"))
                 .unwrap_or_else(|e| format!("Error: {}", e))
         }
+        // Marker unpacked by image_block() into an image content block instead of a text tool_result.
+        "read_image" => {
+            let path = input["path"].as_str().unwrap_or("");
+            let loaded: Result<(String, Vec<u8>), String> = if let Some(rest) = path.strip_prefix("data:") {
+                let mut parts = rest.splitn(2, ',');
+                let header = parts.next().unwrap_or("");
+                let data = parts.next().unwrap_or("");
+                let media_type = header.split(';').next().unwrap_or("image/png").to_string();
+                base64::engine::general_purpose::STANDARD.decode(data).map(|b| (media_type, b)).map_err(|e| format!("Error: {}", e))
+            } else {
+                let media_type = match path.rsplit('.').next().unwrap_or("") {
+                    "png" => "image/png",
+                    "jpg" | "jpeg" => "image/jpeg",
+                    "gif" => "image/gif",
+                    "webp" => "image/webp",
+                    _ => "application/octet-stream",
+                }.to_string();
+                fs::read(path).map(|b| (media_type, b)).map_err(|e| format!("Error: {}", e))
+            };
+            loaded.map(|(media_type, bytes)| json!({
+                "__image__": true, "media_type": media_type, "data": base64::engine::general_purpose::STANDARD.encode(&bytes)
+            }).to_string()).unwrap_or_else(|e| e)
+        }
         _ => "Unknown tool".to_string()
     }
 }
 
-fn call(client: &ureq::Agent, url: &str, key: &str, messages: &[Message], model: &str) -> Result<Response, String> {
+fn call(client: &ureq::Agent, url: &str, key: &str, messages: &[Message], model: &str, provider: Provider) -> Result<Response, String> {
+    let tools: Value = serde_json::from_str(TOOLS).unwrap();
+    let system = "You are a coding assistant. Use tools to help.";
+
+    let body = match provider {
+        Provider::Anthropic => json!(Request { model: model.to_string(), max_tokens: 8192, tools, messages: messages.to_vec(), system: system.to_string(), stream: false }),
+        Provider::OpenAI => json!({"model": model, "max_tokens": 8192, "messages": openai_messages(system, messages), "tools": openai_tools(&tools)}),
+        Provider::Cohere => cohere_body(model, system, &cohere_tools(&tools), messages),
+    };
+
+    let req = client.post(url).set("Content-Type", "application/json");
+    let req = match provider {
+        Provider::Anthropic => req.set("x-api-key", key).set("anthropic-version", "2023-06-01"),
+        Provider::OpenAI | Provider::Cohere => req.set("Authorization", &format!("Bearer {}", key)),
+    };
+    let resp: Value = req.send_json(&body).map_err(|e| format!("API error: {}", e))?
+        .into_json().map_err(|e| format!("Parse error: {}", e))?;
+
+    match provider {
+        Provider::Anthropic => serde_json::from_value(resp).map_err(|e| format!("Parse error: {}", e)),
+        Provider::OpenAI => parse_openai(resp),
+        Provider::Cohere => parse_cohere(resp),
+    }
+}
+
+// Same request as `call`, but reads the SSE stream and prints text as it arrives.
+fn call_stream(client: &ureq::Agent, url: &str, key: &str, messages: &[Message], model: &str) -> Result<Response, String> {
     let tools: Value = serde_json::from_str(TOOLS).unwrap();
-    let req = Request { model: model.to_string(), max_tokens: 8192, tools, messages: messages.to_vec(), system: "You are a coding assistant. Use tools to help.".to_string() };
+    let req = Request { model: model.to_string(), max_tokens: 8192, tools, messages: messages.to_vec(), system: "You are a coding assistant. Use tools to help.".to_string(), stream: true };
 
-    client.post(url)
+    let resp = client.post(url)
         .set("Content-Type", "application/json")
         .set("x-api-key", key)
         .set("anthropic-version", "2023-06-01")
         .send_json(&req)
-        .map_err(|e| format!("API error: {}", e))?
-        .into_json::<Response>()
-        .map_err(|e| format!("Parse error: {}", e))
+        .map_err(|e| format!("API error: {}", e))?;
+
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut partial_json: std::collections::HashMap<usize, String> = std::collections::HashMap::new();
+    let mut stop_reason = String::new();
+
+    for line in std::io::BufRead::lines(std::io::BufReader::new(resp.into_reader())) {
+        let line = line.map_err(|e| format!("Stream error: {}", e))?;
+        let Some(data) = line.strip_prefix("data: ") else { continue };
+        let event: Value = match serde_json::from_str(data) { Ok(v) => v, Err(_) => continue };
+        let index = event["index"].as_u64().unwrap_or(0) as usize;
+
+        match event["type"].as_str().unwrap_or("") {
+            "content_block_start" => {
+                let cb = &event["content_block"];
+                let r#type = cb["type"].as_str().unwrap_or("text").to_string();
+                if blocks.len() <= index { blocks.resize_with(index + 1, || Block { r#type: "text".to_string(), id: None, name: None, input: None, text: None }); }
+                blocks[index] = Block {
+                    id: cb["id"].as_str().map(|s| s.to_string()),
+                    name: cb["name"].as_str().map(|s| s.to_string()),
+                    input: None,
+                    text: if r#type == "text" { Some(String::new()) } else { None },
+                    r#type,
+                };
+                partial_json.insert(index, String::new());
+            }
+            "content_block_delta" => {
+                match event["delta"]["type"].as_str().unwrap_or("") {
+                    "text_delta" => {
+                        let text = event["delta"]["text"].as_str().unwrap_or("");
+                        print!("{}", text);
+                        std::io::stdout().flush().ok();
+                        if let Some(b) = blocks.get_mut(index) { b.text.get_or_insert_with(String::new).push_str(text); }
+                    }
+                    "input_json_delta" => {
+                        let partial = event["delta"]["partial_json"].as_str().unwrap_or("");
+                        partial_json.entry(index).or_default().push_str(partial);
+                    }
+                    _ => {}
+                }
+            }
+            "content_block_stop" => {
+                if let Some(json_str) = partial_json.get(&index).filter(|s| !s.is_empty()) {
+                    if let Some(b) = blocks.get_mut(index) { b.input = serde_json::from_str(json_str).ok(); }
+                }
+            }
+            "message_delta" => {
+                if let Some(sr) = event["delta"]["stop_reason"].as_str() { stop_reason = sr.to_string(); }
+            }
+            "error" => {
+                return Err(format!("API error: {}", event["error"]["message"].as_str().unwrap_or("stream error")));
+            }
+            _ => {}
+        }
+    }
+    println!();
+
+    if stop_reason.is_empty() {
+        return Err("Stream error: connection ended before a stop_reason was received".to_string());
+    }
+
+    Ok(Response { content: blocks, stop_reason })
+}
+
+// Unpacks a `read_image` marker into an Anthropic image content block, if `result` is one.
+fn image_block(result: &str) -> Option<Value> {
+    let marker: Value = serde_json::from_str(result).ok()?;
+    marker.get("__image__")?;
+    Some(json!([{"type": "image", "source": {"type": "base64", "media_type": marker["media_type"], "data": marker["data"]}}]))
+}
+
+// Reuses an earlier tool_result for the same (name, input) read-only tool_use, if any.
+// NEEDS_APPROVAL tools are excluded: a cache hit would skip their approval prompt.
+fn cached_result(history: &[Message], name: &str, input: &Value) -> Option<String> {
+    if NEEDS_APPROVAL.contains(&name) { return None; }
+    for (i, m) in history.iter().enumerate() {
+        if m.role != "assistant" { continue; }
+        let Some(blocks) = m.content.as_array() else { continue };
+        for b in blocks {
+            if b["type"] != "tool_use" || b["name"].as_str() != Some(name) || &b["input"] != input { continue; }
+            let Some(tool_use_id) = b["id"].as_str() else { continue };
+            let Some(results) = history.get(i + 1).and_then(|next| next.content.as_array()) else { continue };
+            if let Some(r) = results.iter().find(|r| r["tool_use_id"].as_str() == Some(tool_use_id)) {
+                return r["content"].as_str().map(|s| s.to_string());
+            }
+        }
+    }
+    None
 }
 
-fn agent(prompt: &str, url: &str, key: &str, model: &str) -> Result<String, String> {
+// Tools that mutate the filesystem or execute commands need a human in the loop.
+const NEEDS_APPROVAL: [&str; 3] = ["write_file", "edit_file", "bash"];
+
+fn preview(name: &str, input: &Value) -> String {
+    match name {
+        "bash" => input["command"].as_str().unwrap_or("").to_string(),
+        "write_file" => format!("{} ({} bytes)", input["path"].as_str().unwrap_or(""), input["content"].as_str().unwrap_or("").len()),
+        "edit_file" => format!("{}\n- {}\n+ {}", input["path"].as_str().unwrap_or(""), input["old_string"].as_str().unwrap_or(""), input["new_string"].as_str().unwrap_or("")),
+        _ => input.to_string(),
+    }
+}
+
+// Prompts for approval unless YOLO=1 is set.
+fn confirm(name: &str, input: &Value) -> bool {
+    if env::var("YOLO").map(|v| v == "1").unwrap_or(false) { return true; }
+    println!("⚠ {} {}", name, preview(name, input));
+    print!("Proceed? [y/N] ");
+    std::io::stdout().flush().ok();
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line).ok();
+    line.trim().eq_ignore_ascii_case("y")
+}
+
+// Runs one turn's tool_use blocks concurrently; results are written back by index so
+// tool_use_id pairing survives regardless of completion order.
+fn run_tools_parallel(blocks: &[Block], history: &[Message]) -> Vec<Value> {
+    let tool_blocks: Vec<&Block> = blocks.iter().filter(|b| b.r#type == "tool_use").collect();
+    let cached: Vec<Option<String>> = tool_blocks.iter()
+        .map(|b| cached_result(history, b.name.as_ref().unwrap(), b.input.as_ref().unwrap()))
+        .collect();
+    let approved: Vec<bool> = tool_blocks.iter().zip(&cached).map(|(b, cached)| {
+        let name = b.name.as_ref().unwrap();
+        cached.is_some() || !NEEDS_APPROVAL.contains(&name.as_str()) || confirm(name, b.input.as_ref().unwrap())
+    }).collect();
+
+    let pool_size = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4).min(tool_blocks.len().max(1));
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Value>>> = Mutex::new(vec![None; tool_blocks.len()]);
+
+    std::thread::scope(|scope| {
+        for _ in 0..pool_size {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if i >= tool_blocks.len() { break; }
+                let b = tool_blocks[i];
+                let name = b.name.as_ref().unwrap();
+                let content: Value = if let Some(r) = &cached[i] {
+                    println!("↻ {} (cached)", name);
+                    json!(r)
+                } else if approved[i] {
+                    println!("⚡ {}", name);
+                    let r = run(name, b.input.as_ref().unwrap());
+                    println!("{}", &r[..r.len().min(100)]);
+                    image_block(&r).unwrap_or_else(|| json!(r))
+                } else {
+                    json!("Cancelled by user")
+                };
+                results.lock().unwrap()[i] = Some(json!({"type": "tool_result", "tool_use_id": b.id, "content": content}));
+            });
+        }
+    });
+
+    results.into_inner().unwrap().into_iter().map(|r| r.unwrap()).collect()
+}
+
+fn load_session(path: &str) -> Vec<Message> {
+    fs::read_to_string(path).ok().and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default()
+}
+
+fn save_session(path: &str, messages: &[Message]) {
+    if let Ok(s) = serde_json::to_string(messages) { let _ = fs::write(path, s); }
+}
+
+fn agent(prompt: &str, url: &str, key: &str, model: &str, provider: Provider, session_path: Option<&str>) -> Result<String, String> {
     let client = ureq::agent();
-    let mut messages = vec![Message { role: "user".to_string(), content: json!(prompt) }];
+    let streaming = provider == Provider::Anthropic && env::var("STREAM").map(|v| v == "1").unwrap_or(false);
+    let mut messages = session_path.map(load_session).unwrap_or_default();
+    // A resumed session may already end in a user turn; merge the new prompt into it rather
+    // than push a second consecutive user message, which the API rejects.
+    match messages.last_mut().filter(|m| m.role == "user") {
+        Some(last) => {
+            let mut parts = match last.content.take() {
+                Value::Array(a) => a,
+                Value::String(s) => vec![json!({"type": "text", "text": s})],
+                other => vec![other],
+            };
+            parts.push(json!({"type": "text", "text": prompt}));
+            last.content = json!(parts);
+        }
+        None => messages.push(Message { role: "user".to_string(), content: json!(prompt) }),
+    }
 
     loop {
-        let res = call(&client, url, key, &messages, model)?;
+        let res = if streaming { call_stream(&client, url, key, &messages, model)? } else { call(&client, url, key, &messages, model, provider)? };
         messages.push(Message { role: "assistant".to_string(), content: json!(res.content) });
+        if let Some(p) = session_path { save_session(p, &messages); }
 
         if res.stop_reason != "tool_use" {
             return Ok(res.content.iter().filter(|b| b.r#type == "text").filter_map(|b| b.text.clone()).collect::<Vec<_>>().join(""));
         }
 
-        let results: Vec<Value> = res.content.iter().filter(|b| b.r#type == "tool_use").map(|b| {
-            let name = b.name.as_ref().unwrap();
-            println!("⚡ {}", name);
-            let r = run(name, b.input.as_ref().unwrap());
-            println!("{}", &r[..r.len().min(100)]);
-            json!({"type": "tool_result", "tool_use_id": b.id, "content": r})
-        }).collect();
+        let results = run_tools_parallel(&res.content, &messages);
 
         messages.push(Message { role: "user".to_string(), content: json!(results) });
+        if let Some(p) = session_path { save_session(p, &messages); }
     }
 }
 
 fn main() {
-    let args: Vec<String> = env::args().skip(1).collect();
-    if args.is_empty() { eprintln!("Usage: nano \"your prompt\""); std::process::exit(1); }
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mut session_path = env::var("SESSION").ok();
+    if let Some(pos) = args.iter().position(|a| a == "--session") {
+        args.remove(pos);
+        if pos < args.len() { session_path = Some(args.remove(pos)); }
+    }
+    if args.is_empty() { eprintln!("Usage: nano \"your prompt\" [--session path]"); std::process::exit(1); }
 
-    let key = env::var("ANTHROPIC_API_KEY").or_else(|_| env::var("ANTHROPIC_AUTH_TOKEN")).unwrap_or_default();
-    if key.is_empty() { eprintln!("Set ANTHROPIC_API_KEY or ANTHROPIC_AUTH_TOKEN"); std::process::exit(1); }
+    let provider = Provider::from_env();
+    let (key, url) = match provider {
+        Provider::Anthropic => {
+            let key = env::var("ANTHROPIC_API_KEY").or_else(|_| env::var("ANTHROPIC_AUTH_TOKEN")).unwrap_or_default();
+            let base = env::var("ANTHROPIC_BASE_URL").unwrap_or_else(|_| "https://api.anthropic.com".to_string());
+            (key, format!("{}/v1/messages", base.trim_end_matches('/')))
+        }
+        Provider::OpenAI => {
+            let key = env::var("OPENAI_API_KEY").unwrap_or_default();
+            let base = env::var("OPENAI_BASE_URL").unwrap_or_else(|_| "https://api.openai.com".to_string());
+            (key, format!("{}/v1/chat/completions", base.trim_end_matches('/')))
+        }
+        Provider::Cohere => {
+            let key = env::var("COHERE_API_KEY").unwrap_or_default();
+            let base = env::var("COHERE_BASE_URL").unwrap_or_else(|_| "https://api.cohere.com".to_string());
+            (key, format!("{}/v1/chat", base.trim_end_matches('/')))
+        }
+    };
+    if key.is_empty() { eprintln!("Set the API key env var for PROVIDER (ANTHROPIC_API_KEY / OPENAI_API_KEY / COHERE_API_KEY)"); std::process::exit(1); }
 
-    let base = env::var("ANTHROPIC_BASE_URL").unwrap_or_else(|_| "https://api.anthropic.com".to_string());
-    let url = format!("{}/v1/messages", base.trim_end_matches('/'));
-    let model = env::var("MODEL").unwrap_or_else(|_| "claude-sonnet-4-20250514".to_string());
+    let model = env::var("MODEL").unwrap_or_else(|_| provider.default_model().to_string());
 
-    match agent(&args.join(" "), &url, &key, &model) {
+    match agent(&args.join(" "), &url, &key, &model, provider, session_path.as_deref()) {
         Ok(result) => println!("{}", result),
         Err(e) => eprintln!("Error: {}", e),
     }